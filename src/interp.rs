@@ -1,42 +1,162 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
 
-use crate::types::{self, QObject, QType};
+use crate::types::{self, QFuncBody, QObject, QType};
 
-pub struct QInterp {
+struct Inner {
     globals: QObject,
     locals: Vec<QObject>,
+    current_thread: usize,
 }
 
+/// A cheap-to-clone handle onto the interpreter's state. Cloning shares the
+/// same globals/scope stack (it's an `Rc<RefCell<..>>` underneath, not a deep
+/// copy), which is what lets a lazily-evaluated `Stream` (see `iter.rs`'s
+/// `qmap`/`qfilter`) capture an owned `QInterp` and keep calling back into it
+/// on each `next()`, long after the builtin that produced the Stream has
+/// returned.
+#[derive(Clone)]
+pub struct QInterp(Rc<RefCell<Inner>>);
+
 impl QInterp {
     pub fn init() -> Self {
-        Self {
+        let interp = Self(Rc::new(RefCell::new(Inner {
             globals: QObject::new(),
-            locals: {
-                let mut v = Vec::<QObject>::new();
-                v.push(QObject::new());
-                v
-            },
-        }
+            locals: vec![QObject::new()],
+            current_thread: 0,
+        })));
+        crate::iter::register(&interp);
+        crate::math::register(&interp);
+        interp
+    }
+
+    /// The id a bare `Thread(None)` (`@this`) resolves to when compared for
+    /// equality inside the interpreter that owns it.
+    pub fn current_thread(&self) -> usize {
+        self.0.borrow().current_thread
     }
 
-    pub fn store_global(&mut self, name: String, value: QType) {
-        _ = self.globals.insert(name, value);
+    /// Like `QType::eq`, but first resolves either side's `Thread(None)` to
+    /// this interpreter's `current_thread`, so `@this == @this` is true from
+    /// wherever it's evaluated.
+    pub fn eq(&self, lhs: QType, rhs: QType) -> QType {
+        let resolve = |t: QType| match t {
+            QType::Thread(None) => QType::Thread(Some(self.current_thread())),
+            other => other,
+        };
+        QType::eq(resolve(lhs), resolve(rhs))
+    }
+
+    pub fn store_global(&self, name: String, value: QType) {
+        _ = self.0.borrow_mut().globals.insert(name, value);
     }
 
     pub fn fetch_global(&self, name: String) -> Option<QType> {
-        let val = self.globals.get(&name);
-        if let Some(o) = val {
-            Some(o.clone())
-        } else {
-            None
+        self.0.borrow().globals.get(&name).cloned()
+    }
+
+    pub fn store_local(&self, name: String, value: QType) {
+        let mut inner = self.0.borrow_mut();
+        let last = inner.locals.len() - 1;
+        _ = inner.locals[last].insert(name, value);
+    }
+
+    /// Walks the scope stack from innermost outward, falling back to
+    /// `globals` if no enclosing scope binds `name`.
+    pub fn fetch_local(&self, name: String) -> Option<QType> {
+        for scope in self.0.borrow().locals.iter().rev() {
+            if let Some(v) = scope.get(&name) {
+                return Some(v.clone());
+            }
         }
+        self.fetch_global(name)
     }
 
-    pub fn store_local(&mut self, name: String, value: QType) {
-        let last = self.locals.len()-1;
-        let mut curr_scope = self.locals.get_mut(last);
-        if let Some(s) = curr_scope {
-            _ = s.insert(name, value);
-        } else { unreachable!() }
+    pub fn push_scope(&self) {
+        self.0.borrow_mut().locals.push(QObject::new());
     }
-}
\ No newline at end of file
+
+    pub fn pop_scope(&self) {
+        let mut inner = self.0.borrow_mut();
+        if inner.locals.len() > 1 {
+            inner.locals.pop();
+        }
+    }
+
+    /// Calls `func` with `args`: pushes a fresh scope, binds the closure's
+    /// captures and (for interpreted bodies) its parameters, evaluates, then
+    /// pops the scope again.
+    pub fn call(&self, func: QType, args: Vec<QType>) -> QType {
+        let QType::Func(captures, body) = func else {
+            return types::QType::not_callable();
+        };
+        self.push_scope();
+        for (name, value) in captures {
+            self.store_local(name, value);
+        }
+        let result = match body {
+            QFuncBody::Native(native) => native(self, args),
+            QFuncBody::Interpreted(params, body) => {
+                for (param, arg) in params.into_iter().zip(args) {
+                    self.store_local(param, arg);
+                }
+                (*body).clone()
+            }
+        };
+        self.pop_scope();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fetch_local_reads_the_innermost_scope_first() {
+        let interp = QInterp::init();
+        interp.store_global("x".to_string(), QType::Int(0));
+        interp.push_scope();
+        interp.store_local("x".to_string(), QType::Int(1));
+        interp.push_scope();
+        interp.store_local("x".to_string(), QType::Int(2));
+        assert!(matches!(interp.fetch_local("x".to_string()), Some(QType::Int(2))));
+    }
+
+    #[test]
+    fn fetch_local_walks_outward_when_the_innermost_scope_has_no_binding() {
+        let interp = QInterp::init();
+        interp.push_scope();
+        interp.store_local("x".to_string(), QType::Int(1));
+        interp.push_scope();
+        assert!(matches!(interp.fetch_local("x".to_string()), Some(QType::Int(1))));
+    }
+
+    #[test]
+    fn fetch_local_falls_back_to_globals() {
+        let interp = QInterp::init();
+        interp.store_global("x".to_string(), QType::Int(42));
+        interp.push_scope();
+        assert!(matches!(interp.fetch_local("x".to_string()), Some(QType::Int(42))));
+    }
+
+    #[test]
+    fn fetch_local_misses_return_none() {
+        let interp = QInterp::init();
+        assert!(interp.fetch_local("nope".to_string()).is_none());
+    }
+
+    #[test]
+    fn pop_scope_never_pops_below_the_base_scope() {
+        let interp = QInterp::init();
+        interp.store_global("x".to_string(), QType::Int(1));
+        interp.push_scope();
+        interp.store_local("x".to_string(), QType::Int(2));
+        interp.pop_scope();
+        interp.pop_scope();
+        // A second pop_scope was a no-op: the base scope is still there, so
+        // a local store still lands somewhere instead of panicking.
+        interp.store_local("y".to_string(), QType::Int(3));
+        assert!(matches!(interp.fetch_local("y".to_string()), Some(QType::Int(3))));
+    }
+}
@@ -0,0 +1,452 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::interp::QInterp;
+use crate::types::{QErrorKind, QFuncBody, QObject, QStream, QType};
+
+/// Forces `value` into a `Vec`, reusing the existing coercion machinery. A
+/// propagated `Err` is returned unchanged rather than reported as the
+/// (unrelated) type mismatch named by `expected`.
+fn as_list(value: QType, expected: &str) -> Result<Vec<QType>, QType> {
+    if let e @ QType::Err(_) = value {
+        return Err(e);
+    }
+    match QType::into(value, QType::List(Vec::new())) {
+        QType::List(items) => Ok(items),
+        other @ QType::Err(_) => Err(other),
+        _ => Err(QType::err(QErrorKind::TypeMismatch, expected.to_string())),
+    }
+}
+
+/// Forces `value` into a lazy `QStream` without draining it: a `List` is
+/// wrapped around its own iterator and `Stream`/`Range` keep whatever
+/// laziness they already have, so an infinite `Range` piped through this
+/// stays lazy instead of looping forever up front.
+fn as_stream(value: QType, expected: &str) -> Result<QStream, QType> {
+    if let e @ QType::Err(_) = value {
+        return Err(e);
+    }
+    match value {
+        QType::List(items) => Ok(Rc::new(RefCell::new(items.into_iter()))),
+        v @ (QType::Stream(_) | QType::Range { .. }) => match QType::to_stream(v) {
+            QType::Stream(s) => Ok(s),
+            other @ QType::Err(_) => Err(other),
+            _ => unreachable!("to_stream only ever returns Stream or Err"),
+        },
+        _ => Err(QType::err(QErrorKind::TypeMismatch, expected.to_string())),
+    }
+}
+
+/// Lazily applies `f` to each item pulled from `inner`, one at a time.
+struct MapStream {
+    inner: QStream,
+    f: QType,
+    interp: QInterp,
+}
+
+impl Iterator for MapStream {
+    type Item = QType;
+
+    fn next(&mut self) -> Option<QType> {
+        let item = self.inner.borrow_mut().next()?;
+        Some(self.interp.call(self.f.clone(), vec![item]))
+    }
+}
+
+/// Lazily yields only the items pulled from `inner` that satisfy `predicate`.
+struct FilterStream {
+    inner: QStream,
+    predicate: QType,
+    interp: QInterp,
+}
+
+impl Iterator for FilterStream {
+    type Item = QType;
+
+    fn next(&mut self) -> Option<QType> {
+        loop {
+            let item = self.inner.borrow_mut().next()?;
+            let keep = self.interp.call(self.predicate.clone(), vec![item.clone()]);
+            if let QType::Bool(true) = QType::into(keep, QType::Bool(false)) {
+                return Some(item);
+            }
+        }
+    }
+}
+
+/// Lazily pairs up items pulled one at a time from `a` and `b`, stopping as
+/// soon as either side is exhausted.
+struct ZipStream {
+    a: QStream,
+    b: QStream,
+}
+
+impl Iterator for ZipStream {
+    type Item = QType;
+
+    fn next(&mut self) -> Option<QType> {
+        let x = self.a.borrow_mut().next()?;
+        let y = self.b.borrow_mut().next()?;
+        Some(QType::List(vec![x, y]))
+    }
+}
+
+/// Lazily pairs each item pulled from `inner` with its index.
+struct EnumerateStream {
+    inner: QStream,
+    index: i64,
+}
+
+impl Iterator for EnumerateStream {
+    type Item = QType;
+
+    fn next(&mut self) -> Option<QType> {
+        let item = self.inner.borrow_mut().next()?;
+        let index = self.index;
+        self.index += 1;
+        Some(QType::List(vec![QType::Int(index), item]))
+    }
+}
+
+fn qmap(interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 2 {
+        return QType::err(QErrorKind::TypeMismatch, "map expects (collection, f)");
+    }
+    let f = args.remove(1);
+    let inner = match as_stream(args.remove(0), "map expects a List, Stream, or Range as its collection") {
+        Ok(inner) => inner,
+        Err(e) => return e,
+    };
+    QType::Stream(Rc::new(RefCell::new(MapStream { inner, f, interp: interp.clone() })))
+}
+
+fn qfilter(interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 2 {
+        return QType::err(QErrorKind::TypeMismatch, "filter expects (collection, predicate)");
+    }
+    let predicate = args.remove(1);
+    let inner = match as_stream(args.remove(0), "filter expects a List, Stream, or Range as its collection") {
+        Ok(inner) => inner,
+        Err(e) => return e,
+    };
+    QType::Stream(Rc::new(RefCell::new(FilterStream { inner, predicate, interp: interp.clone() })))
+}
+
+fn qfold(interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 3 {
+        return QType::err(QErrorKind::TypeMismatch, "fold expects (collection, init, f)");
+    }
+    let f = args.remove(2);
+    let init = args.remove(1);
+    let items = match as_list(args.remove(0), "fold expects a List, Stream, or Range as its collection") {
+        Ok(items) => items,
+        Err(e) => return e,
+    };
+    items
+        .into_iter()
+        .fold(init, |acc, item| interp.call(f.clone(), vec![acc, item]))
+}
+
+fn qreduce(interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 2 {
+        return QType::err(QErrorKind::TypeMismatch, "reduce expects (collection, f)");
+    }
+    let f = args.remove(1);
+    let mut items = match as_list(args.remove(0), "reduce expects a List, Stream, or Range as its collection") {
+        Ok(items) => items,
+        Err(e) => return e,
+    };
+    if items.is_empty() {
+        return QType::err(QErrorKind::TypeMismatch, "reduce on an empty collection");
+    }
+    let first = items.remove(0);
+    items
+        .into_iter()
+        .fold(first, |acc, item| interp.call(f.clone(), vec![acc, item]))
+}
+
+fn qzip(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 2 {
+        return QType::err(QErrorKind::TypeMismatch, "zip expects (a, b)");
+    }
+    let b = args.remove(1);
+    let a = args.remove(0);
+    let a = match as_stream(a, "zip expects two Lists, Streams, or Ranges") {
+        Ok(a) => a,
+        Err(e) => return e,
+    };
+    let b = match as_stream(b, "zip expects two Lists, Streams, or Ranges") {
+        Ok(b) => b,
+        Err(e) => return e,
+    };
+    QType::Stream(Rc::new(RefCell::new(ZipStream { a, b })))
+}
+
+fn qenumerate(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 1 {
+        return QType::err(QErrorKind::TypeMismatch, "enumerate expects (collection)");
+    }
+    let inner = match as_stream(args.remove(0), "enumerate expects a List, Stream, or Range") {
+        Ok(inner) => inner,
+        Err(e) => return e,
+    };
+    QType::Stream(Rc::new(RefCell::new(EnumerateStream { inner, index: 0 })))
+}
+
+fn qrange(_interp: &QInterp, args: Vec<QType>) -> QType {
+    let ints: Option<Vec<i64>> = args
+        .iter()
+        .map(|a| match a {
+            QType::Int(i) => Some(*i),
+            _ => None,
+        })
+        .collect();
+    match (args.len(), ints) {
+        (2, Some(v)) => QType::Range {
+            start: v[0],
+            end: v[1],
+            step: 1,
+            inclusive: false,
+        },
+        (3, Some(v)) => QType::Range {
+            start: v[0],
+            end: v[1],
+            step: v[2],
+            inclusive: false,
+        },
+        _ => QType::err(QErrorKind::TypeMismatch, "range expects (start, end) or (start, end, step) ints"),
+    }
+}
+
+fn qlen(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 1 {
+        return QType::err(QErrorKind::TypeMismatch, "len expects (collection)");
+    }
+    match as_list(args.remove(0), "len expects a List, Stream, Range, Str, or Obj") {
+        Ok(items) => QType::Int(items.len() as i64),
+        Err(e) => e,
+    }
+}
+
+fn qsum(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 1 {
+        return QType::err(QErrorKind::TypeMismatch, "sum expects (collection)");
+    }
+    let items = match as_list(args.remove(0), "sum expects a List, Stream, or Range") {
+        Ok(items) => items,
+        Err(e) => return e,
+    };
+    items.into_iter().fold(QType::Int(0), QType::add)
+}
+
+fn qmin(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 1 {
+        return QType::err(QErrorKind::TypeMismatch, "min expects (collection)");
+    }
+    let mut items = match as_list(args.remove(0), "min expects a List, Stream, or Range") {
+        Ok(items) => items,
+        Err(e) => return e,
+    };
+    if items.is_empty() {
+        return QType::err(QErrorKind::TypeMismatch, "min of an empty collection");
+    }
+    let mut best = items.remove(0);
+    for item in items {
+        if let QType::Bool(true) = QType::lt(item.clone(), best.clone()) {
+            best = item;
+        }
+    }
+    best
+}
+
+fn qmax(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 1 {
+        return QType::err(QErrorKind::TypeMismatch, "max expects (collection)");
+    }
+    let mut items = match as_list(args.remove(0), "max expects a List, Stream, or Range") {
+        Ok(items) => items,
+        Err(e) => return e,
+    };
+    if items.is_empty() {
+        return QType::err(QErrorKind::TypeMismatch, "max of an empty collection");
+    }
+    let mut best = items.remove(0);
+    for item in items {
+        if let QType::Bool(true) = QType::gt(item.clone(), best.clone()) {
+            best = item;
+        }
+    }
+    best
+}
+
+fn qsort(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 1 {
+        return QType::err(QErrorKind::TypeMismatch, "sort expects (collection)");
+    }
+    let mut items = match as_list(args.remove(0), "sort expects a List, Stream, or Range") {
+        Ok(items) => items,
+        Err(e) => return e,
+    };
+    items.sort_by(|a, b| match QType::lt(a.clone(), b.clone()) {
+        QType::Bool(true) => std::cmp::Ordering::Less,
+        _ => match QType::gt(a.clone(), b.clone()) {
+            QType::Bool(true) => std::cmp::Ordering::Greater,
+            _ => std::cmp::Ordering::Equal,
+        },
+    });
+    QType::List(items)
+}
+
+fn qreverse(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 1 {
+        return QType::err(QErrorKind::TypeMismatch, "reverse expects (collection)");
+    }
+    let mut items = match as_list(args.remove(0), "reverse expects a List, Stream, or Range") {
+        Ok(items) => items,
+        Err(e) => return e,
+    };
+    items.reverse();
+    QType::List(items)
+}
+
+/// Registers the iterator/higher-order builtins into `interp`'s globals.
+pub fn register(interp: &QInterp) {
+    for (name, f) in [
+        ("map", qmap as fn(&QInterp, Vec<QType>) -> QType),
+        ("filter", qfilter),
+        ("fold", qfold),
+        ("reduce", qreduce),
+        ("zip", qzip),
+        ("enumerate", qenumerate),
+        ("range", qrange),
+        ("len", qlen),
+        ("sum", qsum),
+        ("min", qmin),
+        ("max", qmax),
+        ("sort", qsort),
+        ("reverse", qreverse),
+    ] {
+        interp.store_global(name.to_string(), QType::Func(QObject::new(), QFuncBody::Native(f)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn native(f: fn(&QInterp, Vec<QType>) -> QType) -> QType {
+        QType::Func(QObject::new(), QFuncBody::Native(f))
+    }
+
+    fn double(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+        QType::mul(args.remove(0), QType::Int(2))
+    }
+
+    fn is_even(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+        QType::eq(QType::modulo(args.remove(0), QType::Int(2)), QType::Int(0))
+    }
+
+    fn force(stream: QType) -> Vec<QType> {
+        match QType::into(stream, QType::List(Vec::new())) {
+            QType::List(items) => items,
+            other => panic!("expected a List, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn map_applies_f_to_every_list_item() {
+        let interp = QInterp::init();
+        let out = qmap(&interp, vec![QType::List(vec![QType::Int(1), QType::Int(2)]), native(double)]);
+        assert!(matches!(
+            QType::eq(QType::List(force(out)), QType::List(vec![QType::Int(2), QType::Int(4)])),
+            QType::Bool(true)
+        ));
+    }
+
+    #[test]
+    fn map_over_a_huge_range_stays_lazy() {
+        let interp = QInterp::init();
+        let huge_range = QType::Range {
+            start: 0,
+            end: i64::MAX,
+            step: 1,
+            inclusive: true,
+        };
+        let mapped = qmap(&interp, vec![huge_range, native(double)]);
+        // If map drained the range up front (the bug being regression-tested
+        // here), this call would never return.
+        assert!(matches!(QType::index(mapped, QType::Int(0)), QType::Int(0)));
+    }
+
+    #[test]
+    fn filter_keeps_only_matching_items() {
+        let interp = QInterp::init();
+        let items = QType::List((1..=5).map(QType::Int).collect());
+        let out = qfilter(&interp, vec![items, native(is_even)]);
+        assert!(matches!(
+            QType::eq(QType::List(force(out)), QType::List(vec![QType::Int(2), QType::Int(4)])),
+            QType::Bool(true)
+        ));
+    }
+
+    #[test]
+    fn zip_over_two_streams_stops_at_the_shorter_side() {
+        let interp = QInterp::init();
+        let huge_range = QType::Range {
+            start: 0,
+            end: i64::MAX,
+            step: 1,
+            inclusive: true,
+        };
+        let out = qzip(&interp, vec![huge_range, QType::List(vec![QType::Int(1), QType::Int(2)])]);
+        // Same laziness concern as map: zipping a huge Range against a short
+        // List must not try to drain the Range first.
+        assert!(matches!(
+            QType::eq(
+                QType::List(force(out)),
+                QType::List(vec![
+                    QType::List(vec![QType::Int(0), QType::Int(1)]),
+                    QType::List(vec![QType::Int(1), QType::Int(2)]),
+                ])
+            ),
+            QType::Bool(true)
+        ));
+    }
+
+    #[test]
+    fn sort_orders_a_stream_like_a_list() {
+        let interp = QInterp::init();
+        let range = QType::Range {
+            start: 3,
+            end: 0,
+            step: -1,
+            inclusive: true,
+        };
+        let out = qsort(&interp, vec![range]);
+        assert!(matches!(
+            QType::eq(out, QType::List(vec![QType::Int(0), QType::Int(1), QType::Int(2), QType::Int(3)])),
+            QType::Bool(true)
+        ));
+    }
+
+    #[test]
+    fn min_and_max_work_over_a_list() {
+        let interp = QInterp::init();
+        let items = || QType::List(vec![QType::Int(3), QType::Int(1), QType::Int(2)]);
+        assert!(matches!(qmin(&interp, vec![items()]), QType::Int(1)));
+        assert!(matches!(qmax(&interp, vec![items()]), QType::Int(3)));
+    }
+
+    #[test]
+    fn min_and_max_work_over_a_stream() {
+        let interp = QInterp::init();
+        let range = || QType::Range {
+            start: 0,
+            end: 5,
+            step: 1,
+            inclusive: false,
+        };
+        assert!(matches!(qmin(&interp, vec![range()]), QType::Int(0)));
+        assert!(matches!(qmax(&interp, vec![range()]), QType::Int(4)));
+    }
+}
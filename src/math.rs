@@ -0,0 +1,73 @@
+use crate::interp::QInterp;
+use crate::types::{QErrorKind, QFuncBody, QObject, QType};
+
+fn as_float(value: QType) -> Option<f64> {
+    match value {
+        QType::Int(i) => Some(i as f64),
+        QType::Float(f) => Some(f),
+        _ => None,
+    }
+}
+
+fn qsqrt(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 1 {
+        return QType::err(QErrorKind::TypeMismatch, "sqrt expects (x)");
+    }
+    match as_float(args.remove(0)) {
+        Some(x) => QType::Float(x.sqrt()),
+        None => QType::err(QErrorKind::TypeMismatch, "sqrt expects an Int or Float"),
+    }
+}
+
+fn qfloor(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 1 {
+        return QType::err(QErrorKind::TypeMismatch, "floor expects (x)");
+    }
+    match args.remove(0) {
+        QType::Int(i) => QType::Int(i),
+        QType::Float(f) => QType::Float(f.floor()),
+        _ => QType::err(QErrorKind::TypeMismatch, "floor expects an Int or Float"),
+    }
+}
+
+fn qabs(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 1 {
+        return QType::err(QErrorKind::TypeMismatch, "abs expects (x)");
+    }
+    match args.remove(0) {
+        QType::Int(i) => QType::Int(i.wrapping_abs()),
+        QType::Float(f) => QType::Float(f.abs()),
+        _ => QType::err(QErrorKind::TypeMismatch, "abs expects an Int or Float"),
+    }
+}
+
+fn qpow(_interp: &QInterp, mut args: Vec<QType>) -> QType {
+    if args.len() != 2 {
+        return QType::err(QErrorKind::TypeMismatch, "pow expects (base, exponent)");
+    }
+    let exponent = args.remove(1);
+    let base = args.remove(0);
+    match (base, exponent) {
+        (QType::Int(b), QType::Int(e)) if (0..=u32::MAX as i64).contains(&e) => {
+            QType::Int(b.wrapping_pow(e as u32))
+        }
+        (QType::Int(b), QType::Int(e)) if e >= 0 => QType::Float((b as f64).powf(e as f64)),
+        (QType::Int(b), QType::Int(e)) => QType::Float((b as f64).powi(e as i32)),
+        (base, exponent) => match (as_float(base), as_float(exponent)) {
+            (Some(b), Some(e)) => QType::Float(b.powf(e)),
+            _ => QType::err(QErrorKind::TypeMismatch, "pow expects Int or Float operands"),
+        },
+    }
+}
+
+/// Registers the math builtins into `interp`'s globals.
+pub fn register(interp: &QInterp) {
+    for (name, f) in [
+        ("sqrt", qsqrt as fn(&QInterp, Vec<QType>) -> QType),
+        ("floor", qfloor),
+        ("abs", qabs),
+        ("pow", qpow),
+    ] {
+        interp.store_global(name.to_string(), QType::Func(QObject::new(), QFuncBody::Native(f)));
+    }
+}
@@ -0,0 +1,287 @@
+use crate::types::QType;
+
+/// One of `QType`'s binary operators, identified so a constant-folding pass
+/// can classify and dispatch it without an AST node of its own (quippy has
+/// no expression tree yet — see `QFuncBody::Interpreted` in `types.rs`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Modulo,
+    And,
+    Or,
+    Xor,
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+impl Op {
+    /// Whether `op(a, b) == op(b, a)` for `Int`, `Float` and `Bool` operands.
+    /// `Add` is classified as commutative even though on `Str`/`List` it
+    /// concatenates and on `Obj` it merges, neither of which commutes.
+    pub fn is_commutative(self) -> bool {
+        matches!(self, Op::Add | Op::Mul | Op::And | Op::Or | Op::Xor | Op::Eq | Op::Ne)
+    }
+
+    /// Whether `op(op(a, b), c) == op(a, op(b, c))` for `Int` and `Bool`
+    /// operands. `Float` `Add`/`Mul` round at each step, so regrouping them
+    /// can change the result.
+    pub fn is_associative(self) -> bool {
+        matches!(self, Op::Add | Op::Mul | Op::And | Op::Or | Op::Xor)
+    }
+
+    fn apply(self, lhs: QType, rhs: QType) -> QType {
+        match self {
+            Op::Add => QType::add(lhs, rhs),
+            Op::Sub => QType::sub(lhs, rhs),
+            Op::Mul => QType::mul(lhs, rhs),
+            Op::Div => QType::div(lhs, rhs),
+            Op::Modulo => QType::modulo(lhs, rhs),
+            Op::And => QType::and(lhs, rhs),
+            Op::Or => QType::or(lhs, rhs),
+            Op::Xor => QType::xor(lhs, rhs),
+            Op::Eq => QType::eq(lhs, rhs),
+            Op::Ne => QType::ne(lhs, rhs),
+            Op::Lt => QType::lt(lhs, rhs),
+            Op::Gt => QType::gt(lhs, rhs),
+            Op::Le => QType::le(lhs, rhs),
+            Op::Ge => QType::ge(lhs, rhs),
+        }
+    }
+}
+
+/// The runtime type an AST walker has statically proven for an `Unknown`
+/// operand, if any. Identity rewrites that would otherwise assume a type
+/// (e.g. `x + 0` only holds if `x` is an `Int`, not a `Str` or `List`) are
+/// only applied when this is known to match — see `fold`'s doc comment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KnownType {
+    Int,
+    Float,
+    Bool,
+    Str,
+}
+
+/// One side of a `fold` call. A future AST walker hands `fold` a `Literal`
+/// for subtrees it has already evaluated to a constant, and `Unknown` for
+/// everything else — tagged with an opaque id (so two `Unknown`s that denote
+/// the same syntactic subexpression, the `x` in `x & x`, can be recognized
+/// as equal without quippy needing real expression identity yet) and,
+/// optionally, the type the walker has statically proven for it.
+#[derive(Clone, Debug)]
+pub enum FoldOperand {
+    Literal(QType),
+    Unknown(u64, Option<KnownType>),
+}
+
+/// The outcome of folding `op(lhs, rhs)`.
+#[derive(Clone, Debug)]
+pub enum Fold {
+    /// Both operands were literal (or an identity rewrite reduced the whole
+    /// expression to a literal, e.g. `x * 0`); this is the final value.
+    Value(QType),
+    /// An identity rewrite let the constant side (or, for `x & x`/`x | x`,
+    /// the duplicate) drop out; the expression is equivalent to the left
+    /// operand, unevaluated.
+    Lhs,
+    /// Same as `Lhs`, but the surviving operand is the right one.
+    Rhs,
+    /// Neither a literal evaluation nor an identity rewrite applied; the
+    /// caller should keep evaluating `op(lhs, rhs)` as written.
+    Unfolded,
+}
+
+fn is_int_zero(v: &QType) -> bool {
+    matches!(v, QType::Int(0))
+}
+
+fn is_int_one(v: &QType) -> bool {
+    matches!(v, QType::Int(1))
+}
+
+/// `const op x`, for identities where the constant is on the left. Only
+/// fires when `unknown_ty` proves `x` is the type the identity needs —
+/// e.g. `0 + x` only drops the `0` when `x` is known to be an `Int`,
+/// since `QType::add` rejects mixed `Int`/non-`Int` operands outright.
+fn fold_const_lhs(op: Op, constant: &QType, unknown_ty: Option<KnownType>) -> Option<Fold> {
+    match (op, unknown_ty) {
+        (Op::Add, Some(KnownType::Int)) if is_int_zero(constant) => Some(Fold::Rhs),
+        (Op::Mul, Some(KnownType::Int)) if is_int_one(constant) => Some(Fold::Rhs),
+        (Op::Mul, Some(KnownType::Int)) if is_int_zero(constant) => Some(Fold::Value(QType::Int(0))),
+        (Op::Xor, Some(KnownType::Int)) if is_int_zero(constant) => Some(Fold::Rhs),
+        _ => None,
+    }
+}
+
+/// `x op const`, for identities where the constant is on the right. See
+/// `fold_const_lhs` for why `unknown_ty` must match.
+fn fold_const_rhs(op: Op, constant: &QType, unknown_ty: Option<KnownType>) -> Option<Fold> {
+    match (op, unknown_ty) {
+        (Op::Add, Some(KnownType::Int)) if is_int_zero(constant) => Some(Fold::Lhs),
+        (Op::Sub, Some(KnownType::Int)) if is_int_zero(constant) => Some(Fold::Lhs),
+        (Op::Mul, Some(KnownType::Int)) if is_int_one(constant) => Some(Fold::Lhs),
+        (Op::Mul, Some(KnownType::Int)) if is_int_zero(constant) => Some(Fold::Value(QType::Int(0))),
+        (Op::Xor, Some(KnownType::Int)) if is_int_zero(constant) => Some(Fold::Lhs),
+        _ => None,
+    }
+}
+
+/// `x op x`, for the same syntactic subexpression on both sides. `And`/`Or`
+/// reduce to `x` only when `unknown_ty` proves `x` is an `Int` or `Bool` (the
+/// only operands `and`/`or` accept; for any other type they produce a
+/// `TypeMismatch`), and `Xor`'s result differs by type (`Int(0)` vs
+/// `Bool(false)`), so it needs `unknown_ty` to pick the right literal.
+fn fold_idempotent(op: Op, unknown_ty: Option<KnownType>) -> Option<Fold> {
+    match (op, unknown_ty) {
+        (Op::And, Some(KnownType::Int | KnownType::Bool)) => Some(Fold::Lhs),
+        (Op::Or, Some(KnownType::Int | KnownType::Bool)) => Some(Fold::Lhs),
+        (Op::Xor, Some(KnownType::Int)) => Some(Fold::Value(QType::Int(0))),
+        (Op::Xor, Some(KnownType::Bool)) => Some(Fold::Value(QType::Bool(false))),
+        _ => None,
+    }
+}
+
+/// Folds `op(lhs, rhs)`: evaluates it directly when both sides are literal,
+/// and applies the algebraic identities `x+0`, `x-0`, `x*1`, `x*0`, `x&x`,
+/// `x|x`, `x^x`, and `x^0` when the non-constant side's statically-known
+/// type makes the rewrite sound, reporting `Fold::Unfolded` otherwise so the
+/// caller keeps the original expression.
+///
+/// An `Unknown` with no proven type (`FoldOperand::Unknown(_, None)`) never
+/// participates in an identity rewrite: e.g. `x * 0` only folds to `0` when
+/// `x` is known to be an `Int` (for `Float` it's unsound — `0.0 * NaN` is
+/// `NaN`, not `0.0` — and for anything else `QType::mul` would itself
+/// produce a `TypeMismatch`), so an untyped `x` is left unfolded rather than
+/// risking a wrong or Err-masking result.
+///
+/// A literal/literal evaluation that would itself produce `QType::Err`
+/// (e.g. `"a" + 1`) is also reported as `Unfolded` rather than
+/// `Value(Err(..))`: the real expression still carries a source span for
+/// diagnostics, and the optimizer has none to attach here.
+pub fn fold(op: Op, lhs: FoldOperand, rhs: FoldOperand) -> Fold {
+    match (lhs, rhs) {
+        (FoldOperand::Literal(l), FoldOperand::Literal(r)) => match op.apply(l, r) {
+            QType::Err(_) => Fold::Unfolded,
+            value => Fold::Value(value),
+        },
+        (FoldOperand::Literal(l), FoldOperand::Unknown(_, ty)) => {
+            fold_const_lhs(op, &l, ty).unwrap_or(Fold::Unfolded)
+        }
+        (FoldOperand::Unknown(_, ty), FoldOperand::Literal(r)) => {
+            fold_const_rhs(op, &r, ty).unwrap_or(Fold::Unfolded)
+        }
+        (FoldOperand::Unknown(a, ta), FoldOperand::Unknown(b, tb)) if a == b => {
+            fold_idempotent(op, ta.or(tb)).unwrap_or(Fold::Unfolded)
+        }
+        (FoldOperand::Unknown(_, _), FoldOperand::Unknown(_, _)) => Fold::Unfolded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit(v: QType) -> FoldOperand {
+        FoldOperand::Literal(v)
+    }
+
+    fn unknown(id: u64, ty: Option<KnownType>) -> FoldOperand {
+        FoldOperand::Unknown(id, ty)
+    }
+
+    #[test]
+    fn literal_literal_evaluates_directly() {
+        let result = fold(Op::Add, lit(QType::Int(2)), lit(QType::Int(3)));
+        assert!(matches!(result, Fold::Value(QType::Int(5))));
+    }
+
+    #[test]
+    fn literal_literal_mismatch_is_unfolded_not_an_err_value() {
+        let result = fold(Op::Add, lit(QType::Str("a".to_string())), lit(QType::Int(1)));
+        assert!(matches!(result, Fold::Unfolded));
+    }
+
+    #[test]
+    fn add_zero_identity_requires_known_int() {
+        let x = unknown(1, Some(KnownType::Int));
+        assert!(matches!(fold(Op::Add, x.clone(), lit(QType::Int(0))), Fold::Lhs));
+        assert!(matches!(fold(Op::Add, lit(QType::Int(0)), x), Fold::Rhs));
+    }
+
+    #[test]
+    fn add_zero_identity_does_not_fire_without_a_known_type() {
+        let x = unknown(1, None);
+        assert!(matches!(fold(Op::Add, x.clone(), lit(QType::Int(0))), Fold::Unfolded));
+        assert!(matches!(fold(Op::Add, lit(QType::Int(0)), x), Fold::Unfolded));
+    }
+
+    #[test]
+    fn mul_zero_absorbs_unknown_int_but_not_unknown_float() {
+        let int_x = unknown(1, Some(KnownType::Int));
+        assert!(matches!(
+            fold(Op::Mul, int_x, lit(QType::Int(0))),
+            Fold::Value(QType::Int(0))
+        ));
+        let float_x = unknown(2, Some(KnownType::Float));
+        assert!(matches!(fold(Op::Mul, float_x, lit(QType::Int(0))), Fold::Unfolded));
+    }
+
+    #[test]
+    fn mul_one_identity() {
+        let x = unknown(1, Some(KnownType::Int));
+        assert!(matches!(fold(Op::Mul, x, lit(QType::Int(1))), Fold::Lhs));
+    }
+
+    #[test]
+    fn sub_zero_identity_only_on_the_right() {
+        let x = unknown(1, Some(KnownType::Int));
+        assert!(matches!(fold(Op::Sub, x.clone(), lit(QType::Int(0))), Fold::Lhs));
+        assert!(matches!(fold(Op::Sub, lit(QType::Int(0)), x), Fold::Unfolded));
+    }
+
+    #[test]
+    fn and_or_idempotence_needs_a_known_int_or_bool() {
+        let x = unknown(7, Some(KnownType::Int));
+        assert!(matches!(fold(Op::And, x.clone(), x.clone()), Fold::Lhs));
+        assert!(matches!(fold(Op::Or, x.clone(), x), Fold::Lhs));
+
+        let untyped_x = unknown(8, None);
+        assert!(matches!(fold(Op::And, untyped_x.clone(), untyped_x.clone()), Fold::Unfolded));
+        assert!(matches!(fold(Op::Or, untyped_x.clone(), untyped_x), Fold::Unfolded));
+    }
+
+    #[test]
+    fn xor_idempotence_needs_a_known_type_to_pick_the_right_literal() {
+        let int_x = unknown(1, Some(KnownType::Int));
+        assert!(matches!(fold(Op::Xor, int_x.clone(), int_x), Fold::Value(QType::Int(0))));
+
+        let bool_x = unknown(2, Some(KnownType::Bool));
+        assert!(matches!(
+            fold(Op::Xor, bool_x.clone(), bool_x),
+            Fold::Value(QType::Bool(false))
+        ));
+
+        let untyped_x = unknown(3, None);
+        assert!(matches!(fold(Op::Xor, untyped_x.clone(), untyped_x), Fold::Unfolded));
+    }
+
+    #[test]
+    fn distinct_unknowns_never_fold_as_idempotent() {
+        let a = unknown(1, Some(KnownType::Int));
+        let b = unknown(2, Some(KnownType::Int));
+        assert!(matches!(fold(Op::Xor, a, b), Fold::Unfolded));
+    }
+
+    #[test]
+    fn classifiers_match_algebraic_definitions() {
+        assert!(Op::Add.is_commutative() && Op::Add.is_associative());
+        assert!(!Op::Sub.is_commutative() && !Op::Sub.is_associative());
+        assert!(Op::Eq.is_commutative() && !Op::Eq.is_associative());
+    }
+}
@@ -1,26 +1,266 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
 
-pub type QObject = HashMap<String, QType>;
+use crate::interp::QInterp;
 
-#[derive(Clone, Debug)]
+/// An insertion-ordered `String -> QType` map, the same parallel `cols`/`vals`
+/// shape as nushell's `Record`: iteration and display follow insertion
+/// order, while equality (`QType::eq`) is key-set equality regardless of it.
+#[derive(Clone, Debug, Default)]
+pub struct QObject {
+    cols: Vec<String>,
+    vals: Vec<QType>,
+}
+
+impl QObject {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cols.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&QType> {
+        self.cols.iter().position(|k| k == key).map(|i| &self.vals[i])
+    }
+
+    pub fn insert(&mut self, key: String, value: QType) -> Option<QType> {
+        match self.cols.iter().position(|k| k == &key) {
+            Some(i) => Some(std::mem::replace(&mut self.vals[i], value)),
+            None => {
+                self.cols.push(key);
+                self.vals.push(value);
+                None
+            }
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.cols.iter()
+    }
+
+    pub fn into_keys(self) -> impl Iterator<Item = String> {
+        self.cols.into_iter()
+    }
+
+    pub fn extend(&mut self, other: Self) {
+        for (k, v) in other {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl IntoIterator for QObject {
+    type Item = (String, QType);
+    type IntoIter = std::iter::Zip<std::vec::IntoIter<String>, std::vec::IntoIter<QType>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cols.into_iter().zip(self.vals)
+    }
+}
+
+impl FromIterator<(String, QType)> for QObject {
+    fn from_iter<I: IntoIterator<Item = (String, QType)>>(iter: I) -> Self {
+        let mut obj = Self::new();
+        for (k, v) in iter {
+            obj.insert(k, v);
+        }
+        obj
+    }
+}
+
+/// A shared, consumed-once lazy sequence of values. Reading from a `Stream`
+/// (indexing, forcing it with `to_list`, etc.) advances the underlying
+/// iterator; callers that need to read it more than once must convert it to
+/// a `List` first.
+pub type QStream = Rc<RefCell<dyn Iterator<Item = QType>>>;
+
+/// A location in the original program text that an error can be attached to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QErrorKind {
+    TypeMismatch,
+    DivideByZero,
+    ParseFailed,
+    IndexOutOfBounds,
+    KeyMissing,
+}
+
+/// A structured runtime error: a `kind` for programmatic matching, a
+/// human-readable `message`, and an optional `span` pinpointing where in the
+/// source it occurred.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QError {
+    pub kind: QErrorKind,
+    pub message: String,
+    pub span: Option<Span>,
+}
+
+impl QError {
+    pub fn new(kind: QErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            span: None,
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+}
+
+/// The callable body of a `Func`: either an interpreted body closing over its
+/// parameter names, or a native Rust builtin registered into `QInterp`.
+///
+/// quippy has no expression/AST type yet, so an `Interpreted` body is for now
+/// just the `QType` it evaluates to once its parameters are bound in scope;
+/// once quippy grows a real AST this becomes the node to walk instead.
+#[derive(Clone)]
+pub enum QFuncBody {
+    Native(fn(&QInterp, Vec<QType>) -> QType),
+    Interpreted(Vec<String>, Rc<QType>),
+}
+
+#[derive(Clone)]
 pub enum QType {
     Int(i64),
     Float(f64),
     Bool(bool),
     Str(String),
     Void,
-    Err,
+    Err(Box<QError>),
     List(Vec<QType>),
     Obj(QObject),
     Thread(Option<usize>),
-    Func(QObject, ()),
+    Func(QObject, QFuncBody),
+    Stream(QStream),
+    Range {
+        start: i64,
+        end: i64,
+        step: i64,
+        inclusive: bool,
+    },
+}
+
+impl std::fmt::Debug for QType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(i) => f.debug_tuple("Int").field(i).finish(),
+            Self::Float(n) => f.debug_tuple("Float").field(n).finish(),
+            Self::Bool(b) => f.debug_tuple("Bool").field(b).finish(),
+            Self::Str(s) => f.debug_tuple("Str").field(s).finish(),
+            Self::Void => write!(f, "Void"),
+            Self::Err(e) => f.debug_tuple("Err").field(e).finish(),
+            Self::List(l) => f.debug_tuple("List").field(l).finish(),
+            Self::Obj(o) => f.debug_tuple("Obj").field(o).finish(),
+            Self::Thread(t) => f.debug_tuple("Thread").field(t).finish(),
+            Self::Func(captures, _) => f.debug_tuple("Func").field(captures).field(&"<body>").finish(),
+            Self::Stream(_) => f.debug_tuple("Stream").field(&"<lazy>").finish(),
+            Self::Range {
+                start,
+                end,
+                step,
+                inclusive,
+            } => f
+                .debug_struct("Range")
+                .field("start", start)
+                .field("end", end)
+                .field("step", step)
+                .field("inclusive", inclusive)
+                .finish(),
+        }
+    }
+}
+
+/// Chains two streams without taking ownership of either: the left stream is
+/// drained first, then the right, matching `Vec::extend`'s concatenation
+/// semantics but without materializing either side.
+struct StreamChain {
+    left: QStream,
+    right: QStream,
+}
+
+impl Iterator for StreamChain {
+    type Item = QType;
+
+    fn next(&mut self) -> Option<QType> {
+        let next = self.left.borrow_mut().next();
+        if next.is_some() {
+            next
+        } else {
+            self.right.borrow_mut().next()
+        }
+    }
+}
+
+/// Lazily produces the integers of a `Range`, stepping by `step` (a zero
+/// step yields nothing) and stopping at `end`, inclusive or exclusive as
+/// requested.
+struct RangeIter {
+    current: i64,
+    end: i64,
+    step: i64,
+    inclusive: bool,
+    done: bool,
+}
+
+impl Iterator for RangeIter {
+    type Item = QType;
+
+    fn next(&mut self) -> Option<QType> {
+        if self.done || self.step == 0 {
+            return None;
+        }
+        let in_bounds = if self.step > 0 {
+            if self.inclusive {
+                self.current <= self.end
+            } else {
+                self.current < self.end
+            }
+        } else if self.inclusive {
+            self.current >= self.end
+        } else {
+            self.current > self.end
+        };
+        if !in_bounds {
+            self.done = true;
+            return None;
+        }
+        let value = self.current;
+        match self.current.checked_add(self.step) {
+            Some(next) => self.current = next,
+            None => self.done = true,
+        }
+        Some(QType::Int(value))
+    }
 }
 
 impl QType {
+    /// Builds a `QType::Err` wrapping a fresh, spanless `QError`.
+    pub(crate) fn err(kind: QErrorKind, message: impl Into<String>) -> Self {
+        Self::Err(Box::new(QError::new(kind, message)))
+    }
+
+    pub(crate) fn not_callable() -> Self {
+        Self::err(QErrorKind::TypeMismatch, "cannot call a non-function value")
+    }
+
     pub fn like(lhs: Self, rhs: Self) -> Self {
         match (lhs, rhs) {
             (Self::Void, Self::Void) => Self::Bool(true),
-            (Self::Err, Self::Err) => Self::Bool(true),
+            (Self::Err(_), Self::Err(_)) => Self::Bool(true),
             (Self::Int(_), Self::Int(_)) => Self::Bool(true),
             (Self::Float(_), Self::Float(_)) => Self::Bool(true),
             (Self::Bool(_), Self::Bool(_)) => Self::Bool(true),
@@ -29,25 +269,50 @@ impl QType {
             (Self::Obj(_), Self::Obj(_)) => Self::Bool(true),
             (Self::Func(_, _), Self::Func(_, _)) => Self::Bool(true),
             (Self::Thread(_), Self::Thread(_)) => Self::Bool(true),
+            (Self::Stream(_), Self::Stream(_)) => Self::Bool(true),
+            (Self::Range { .. }, Self::Range { .. }) => Self::Bool(true),
             _ => Self::Bool(false),
         }
     }
 
+    /// Lowers a `Range` into the `Stream` it describes. `Stream`s pass
+    /// through unchanged; everything else is a type mismatch.
+    pub(crate) fn to_stream(expr: Self) -> Self {
+        match expr {
+            Self::Stream(_) => expr,
+            Self::Range {
+                start,
+                end,
+                step,
+                inclusive,
+            } => Self::Stream(Rc::new(RefCell::new(RangeIter {
+                current: start,
+                end,
+                step,
+                inclusive,
+                done: false,
+            }))),
+            _ => Self::err(QErrorKind::TypeMismatch, "expected a Range or a Stream"),
+        }
+    }
+
     pub fn into(lhs: Self, rhs: Self) -> Self {
         if let Self::Bool(true) = Self::like(lhs.clone(), rhs.clone()) {
             lhs
         } else {
             match rhs {
                 Self::Void => Self::Void,
-                Self::Err => Self::Err,
-                Self::Func(_, _) => Self::Err,
-                Self::Thread(_) => Self::Err,
+                e @ Self::Err(_) => e,
+                Self::Func(_, _) => Self::err(QErrorKind::TypeMismatch, "cannot coerce into a function"),
+                Self::Thread(_) => Self::err(QErrorKind::TypeMismatch, "cannot coerce into a thread"),
+                Self::Range { .. } => Self::err(QErrorKind::TypeMismatch, "cannot coerce into a range"),
                 Self::Int(_) => Self::to_int(lhs),
                 Self::Float(_) => Self::to_float(lhs),
                 Self::Bool(_) => Self::to_bool(lhs),
                 Self::Str(_) => Self::to_str(lhs),
                 Self::List(_) => Self::to_list(lhs),
                 Self::Obj(_) => Self::to_obj(lhs),
+                Self::Stream(_) => Self::to_stream(lhs),
             }
         }
     }
@@ -56,14 +321,12 @@ impl QType {
         match expr {
             Self::Bool(b) => Self::Int(b as i64),
             Self::Float(f) => Self::Int(f as i64),
-            Self::Str(s) => {
-                if let Ok(i) = s.parse::<i64>() {
-                    Self::Int(i)
-                } else {
-                    Self::Err
-                }
-            }
-            _ => Self::Err,
+            Self::Str(s) => match s.parse::<i64>() {
+                Ok(i) => Self::Int(i),
+                Err(_) => Self::err(QErrorKind::ParseFailed, format!("cannot parse \"{s}\" as an int")),
+            },
+            e @ Self::Err(_) => e,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot coerce this type to an int"),
         }
     }
 
@@ -71,14 +334,12 @@ impl QType {
         match expr {
             Self::Bool(b) => Self::Float(b as i64 as f64),
             Self::Int(i) => Self::Float(i as f64),
-            Self::Str(s) => {
-                if let Ok(f) = s.parse::<f64>() {
-                    Self::Float(f)
-                } else {
-                    Self::Err
-                }
-            }
-            _ => Self::Err,
+            Self::Str(s) => match s.parse::<f64>() {
+                Ok(f) => Self::Float(f),
+                Err(_) => Self::err(QErrorKind::ParseFailed, format!("cannot parse \"{s}\" as a float")),
+            },
+            e @ Self::Err(_) => e,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot coerce this type to a float"),
         }
     }
 
@@ -88,10 +349,10 @@ impl QType {
             Self::Float(f) => Self::Bool(f != 0.0),
             Self::Str(s) => Self::Bool(s.len() != 0),
             Self::Void => Self::Bool(true),
-            Self::Err => Self::Bool(false),
+            e @ Self::Err(_) => e,
             Self::List(l) => Self::Bool(l.len() != 0),
             Self::Obj(o) => Self::Bool(o.keys().count() != 0),
-            _ => Self::Err,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot coerce this type to a bool"),
         }
     }
 
@@ -99,7 +360,16 @@ impl QType {
         match expr {
             Self::Str(s) => Self::List(s.bytes().map(|b| Self::Int(b as i64)).collect()),
             Self::Obj(o) => Self::List(o.into_keys().map(|k| from_qobj_key(k)).collect()),
-            _ => Self::Err,
+            Self::Range { .. } => Self::to_list(Self::to_stream(expr)),
+            Self::Stream(s) => {
+                let mut drained = Vec::new();
+                while let Some(item) = s.borrow_mut().next() {
+                    drained.push(item);
+                }
+                Self::List(drained)
+            }
+            e @ Self::Err(_) => e,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot coerce this type to a list"),
         }
     }
 
@@ -117,7 +387,14 @@ impl QType {
                     .map(|(i, e)| ((i as i64).to_string(), e))
                     .collect(),
             ),
-            _ => Self::Err,
+            Self::Range { .. } | Self::Stream(_) => {
+                let Self::List(l) = Self::to_list(expr) else {
+                    unreachable!()
+                };
+                Self::to_obj(Self::List(l))
+            }
+            e @ Self::Err(_) => e,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot coerce this type to an object"),
         }
     }
 
@@ -127,10 +404,16 @@ impl QType {
             Self::Float(f) => Self::Str(f.to_string()),
             Self::Bool(b) => Self::Str(b.to_string()),
             Self::Void => Self::Str("()".to_string()),
-            Self::Err => Self::Str("err".to_string()),
+            Self::Err(e) => Self::Str(format!("err: {} ({:?})", e.message, e.kind)),
             Self::Func(_, _) => Self::Str("\\(...) => ...".to_string()),
             Self::Thread(None) => Self::Str("@this".to_string()),
             Self::Thread(Some(t)) => Self::Str(format!("@{}", t)),
+            Self::Range { .. } | Self::Stream(_) => {
+                let Self::List(l) = Self::to_list(expr) else {
+                    unreachable!()
+                };
+                Self::to_str(Self::List(l))
+            }
             Self::List(l) => {
                 let mut s = String::from("[");
                 s.extend(
@@ -174,6 +457,20 @@ impl QType {
 
 impl QType {
     pub fn add(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
+        let lhs = match lhs {
+            r @ Self::Range { .. } => Self::to_stream(r),
+            other => other,
+        };
+        let rhs = match rhs {
+            r @ Self::Range { .. } => Self::to_stream(r),
+            other => other,
+        };
         match (lhs, rhs) {
             (Self::Int(l), Self::Int(r)) => Self::Int(l.wrapping_add(r)),
             (Self::Float(l), Self::Float(r)) => Self::Float(l + r),
@@ -188,63 +485,112 @@ impl QType {
                 obj.extend(r.clone());
                 obj
             }),
-            _ => Self::Err,
+            // Streams chain instead of cloning + extending: neither side is
+            // materialized, so concatenating two infinite streams still works.
+            (Self::Stream(l), Self::Stream(r)) => {
+                Self::Stream(Rc::new(RefCell::new(StreamChain { left: l, right: r })))
+            }
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot add these types"),
         }
     }
 
     pub fn sub(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
         match (lhs, rhs) {
             (Self::Int(l), Self::Int(r)) => Self::Int(l.wrapping_sub(r)),
             (Self::Float(l), Self::Float(r)) => Self::Float(l - r),
-            _ => Self::Err,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot subtract these types"),
         }
     }
 
     pub fn mul(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
         match (lhs, rhs) {
             (Self::Int(l), Self::Int(r)) => Self::Int(l.wrapping_mul(r)),
             (Self::Float(l), Self::Float(r)) => Self::Float(l * r),
-            _ => Self::Err,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot multiply these types"),
         }
     }
 
     pub fn div(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
         match (lhs, rhs) {
+            (Self::Int(_), Self::Int(0)) => Self::err(QErrorKind::DivideByZero, "division by zero"),
             (Self::Int(l), Self::Int(r)) => Self::Int(l.wrapping_div(r)),
             (Self::Float(l), Self::Float(r)) => Self::Float(l / r),
-            _ => Self::Err,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot divide these types"),
         }
     }
 
     pub fn modulo(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
         match (lhs, rhs) {
+            (Self::Int(_), Self::Int(0)) => Self::err(QErrorKind::DivideByZero, "modulo by zero"),
             (Self::Int(l), Self::Int(r)) => Self::Int(l.wrapping_rem(r)),
             (Self::Float(l), Self::Float(r)) => Self::Float(l % r),
-            _ => Self::Err,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot modulo these types"),
         }
     }
 
     pub fn and(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
         match (lhs, rhs) {
             (Self::Int(l), Self::Int(r)) => Self::Int(l & r),
             (Self::Bool(l), Self::Bool(r)) => Self::Bool(l && r),
-            _ => Self::Err,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot and these types"),
         }
     }
 
     pub fn or(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
         match (lhs, rhs) {
             (Self::Int(l), Self::Int(r)) => Self::Int(l | r),
             (Self::Bool(l), Self::Bool(r)) => Self::Bool(l || r),
-            _ => Self::Err,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot or these types"),
         }
     }
 
     pub fn xor(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
         match (lhs, rhs) {
             (Self::Int(l), Self::Int(r)) => Self::Int(l ^ r),
             (Self::Bool(l), Self::Bool(r)) => Self::Bool(l != r),
-            _ => Self::Err,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot xor these types"),
         }
     }
 
@@ -252,11 +598,18 @@ impl QType {
         match expr {
             Self::Int(i) => Self::Int(!i),
             Self::Bool(b) => Self::Bool(!b),
-            _ => Self::Err,
+            e @ Self::Err(_) => e,
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot negate this type"),
         }
     }
 
     pub fn index(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
         match lhs {
             Self::List(l) => {
                 if let Self::Int(idx) = rhs
@@ -264,31 +617,68 @@ impl QType {
                 {
                     match l.get(idx as usize) {
                         Some(e) => e.clone(),
-                        None => Self::Err,
+                        None => Self::err(
+                            QErrorKind::IndexOutOfBounds,
+                            format!("index {idx} out of bounds for list of length {}", l.len()),
+                        ),
                     }
                 } else {
-                    Self::Err
+                    Self::err(QErrorKind::TypeMismatch, "list index must be a non-negative int")
                 }
             }
             Self::Obj(o) => {
+                if !matches!(rhs, Self::Int(_) | Self::Str(_)) {
+                    return Self::err(QErrorKind::TypeMismatch, "object index must be an int or str");
+                }
                 let key = to_qobj_key(rhs);
                 match o.get(&key) {
                     Some(v) => v.clone(),
-                    None => Self::Err,
+                    None => Self::err(QErrorKind::KeyMissing, format!("key {key} not found")),
+                }
+            }
+            // Indexing a stream advances it to position `idx`, discarding
+            // everything read along the way: streams are consumed-once.
+            Self::Stream(s) => {
+                if let Self::Int(idx) = rhs
+                    && idx >= 0
+                {
+                    match s.borrow_mut().nth(idx as usize) {
+                        Some(e) => e,
+                        None => Self::err(
+                            QErrorKind::IndexOutOfBounds,
+                            format!("index {idx} out of bounds for stream"),
+                        ),
+                    }
+                } else {
+                    Self::err(QErrorKind::TypeMismatch, "stream index must be a non-negative int")
                 }
             }
-            _ => Self::Err,
+            Self::Range { .. } => Self::index(Self::to_stream(lhs), rhs),
+            _ => Self::err(QErrorKind::TypeMismatch, "cannot index this type"),
         }
     }
 
     pub fn eq(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
+        let lhs = match lhs {
+            r @ Self::Range { .. } => Self::to_stream(r),
+            other => other,
+        };
+        let rhs = match rhs {
+            r @ Self::Range { .. } => Self::to_stream(r),
+            other => other,
+        };
         match (lhs, rhs) {
             (Self::Int(l), Self::Int(r)) => Self::Bool(l == r),
             (Self::Float(l), Self::Float(r)) => Self::Bool(l == r),
             (Self::Bool(l), Self::Bool(r)) => Self::Bool(l == r),
             (Self::Str(l), Self::Str(r)) => Self::Bool(l == r),
             (Self::Void, Self::Void) => Self::Bool(true),
-            (Self::Err, Self::Err) => Self::Bool(true),
             (Self::List(l), Self::List(r)) => {
                 if l.len() == r.len() {
                     for (first, second) in l.into_iter().zip(r) {
@@ -302,15 +692,45 @@ impl QType {
                     Self::Bool(false)
                 }
             }
-            (Self::Obj(l), Self::Obj(r)) => todo!(),
+            // Structural equality: the same key set mapping to pairwise-`eq`
+            // values, regardless of insertion order.
+            (Self::Obj(l), Self::Obj(r)) => {
+                if l.len() != r.len() {
+                    return Self::Bool(false);
+                }
+                for key in l.keys() {
+                    let Some(rv) = r.get(key) else {
+                        return Self::Bool(false);
+                    };
+                    let lv = l.get(key).unwrap().clone();
+                    if let Self::Bool(false) = Self::eq(lv, rv.clone()) {
+                        return Self::Bool(false);
+                    }
+                }
+                Self::Bool(true)
+            }
             (Self::Thread(l), Self::Thread(r)) => match (l, r) {
                 (Some(first), Some(second)) => Self::Bool(first == second),
                 (None, None) => Self::Bool(true),
-                (Some(defined), None) | (None, Some(defined)) => todo!(
-                    "Define some mechanism to determine if a defined global thread number is the current thread"
-                ),
+                // Ambiguous without an interpreter's current-thread context:
+                // use `QInterp::eq` to resolve a bare `@this` first.
+                (Some(_), None) | (None, Some(_)) => Self::Bool(false),
             },
             (Self::Func(_, _), Self::Func(_, _)) => Self::Bool(false),
+            // Lazily compare element-by-element, consuming both streams as
+            // we go; bail out (without draining the rest) on the first
+            // mismatch or as soon as one side runs dry before the other.
+            (Self::Stream(l), Self::Stream(r)) => loop {
+                match (l.borrow_mut().next(), r.borrow_mut().next()) {
+                    (None, None) => break Self::Bool(true),
+                    (Some(first), Some(second)) => {
+                        if let Self::Bool(false) = Self::eq(first, second) {
+                            break Self::Bool(false);
+                        }
+                    }
+                    _ => break Self::Bool(false),
+                }
+            },
             _ => Self::Bool(false),
         }
     }
@@ -318,11 +738,25 @@ impl QType {
     pub fn ne(lhs: Self, rhs: Self) -> Self {
         match Self::eq(lhs, rhs) {
             Self::Bool(b) => Self::Bool(!b),
-            _ => Self::Err,
+            other => other,
         }
     }
 
     pub fn lt(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
+        let lhs = match lhs {
+            r @ Self::Range { .. } => Self::to_stream(r),
+            other => other,
+        };
+        let rhs = match rhs {
+            r @ Self::Range { .. } => Self::to_stream(r),
+            other => other,
+        };
         match (lhs, rhs) {
             (Self::Int(l), Self::Int(r)) => Self::Bool(l < r),
             (Self::Float(l), Self::Float(r)) => Self::Bool(l < r),
@@ -331,11 +765,70 @@ impl QType {
                 Self::Bool(false)
             }
             (Self::Thread(Some(l)), Self::Thread(Some(r))) => Self::Bool(l < r),
+            // Lexicographic comparison, giving lists a total order so they
+            // can be sorted or deduplicated like any other key.
+            (Self::List(l), Self::List(r)) => {
+                for (first, second) in l.iter().zip(r.iter()) {
+                    if let Self::Bool(true) = Self::lt(first.clone(), second.clone()) {
+                        return Self::Bool(true);
+                    }
+                    if let Self::Bool(false) = Self::eq(first.clone(), second.clone()) {
+                        return Self::Bool(false);
+                    }
+                }
+                Self::Bool(l.len() < r.len())
+            }
+            // Ordered first by sorted key set, then by value at the first
+            // differing key.
+            (Self::Obj(l), Self::Obj(r)) => {
+                let mut lkeys: Vec<&String> = l.keys().collect();
+                let mut rkeys: Vec<&String> = r.keys().collect();
+                lkeys.sort();
+                rkeys.sort();
+                if lkeys != rkeys {
+                    return Self::Bool(lkeys < rkeys);
+                }
+                for key in lkeys {
+                    let lv = l.get(key).unwrap().clone();
+                    let rv = r.get(key).unwrap().clone();
+                    if let Self::Bool(true) = Self::lt(lv.clone(), rv.clone()) {
+                        return Self::Bool(true);
+                    }
+                    if let Self::Bool(false) = Self::eq(lv, rv) {
+                        return Self::Bool(false);
+                    }
+                }
+                Self::Bool(false)
+            }
+            // Lexicographic lazy comparison: the first differing element
+            // decides; if one stream runs out first, the shorter prefix is
+            // "less", matching how `Vec`/`String` ordering behaves.
+            (Self::Stream(l), Self::Stream(r)) => loop {
+                match (l.borrow_mut().next(), r.borrow_mut().next()) {
+                    (None, None) => break Self::Bool(false),
+                    (None, Some(_)) => break Self::Bool(true),
+                    (Some(_), None) => break Self::Bool(false),
+                    (Some(first), Some(second)) => {
+                        if let Self::Bool(true) = Self::lt(first.clone(), second.clone()) {
+                            break Self::Bool(true);
+                        }
+                        if let Self::Bool(false) = Self::eq(first, second) {
+                            break Self::Bool(false);
+                        }
+                    }
+                }
+            },
             _ => Self::Bool(false),
         }
     }
 
     pub fn gt(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
         match (lhs, rhs) {
             (Self::Int(l), Self::Int(r)) => Self::Bool(l > r),
             (Self::Float(l), Self::Float(r)) => Self::Bool(l > r),
@@ -344,11 +837,50 @@ impl QType {
                 Self::Bool(false)
             }
             (Self::Thread(Some(l)), Self::Thread(Some(r))) => Self::Bool(l > r),
+            // See `lt`'s List/Obj arms for the ordering rules; `gt` is their
+            // mirror image.
+            (Self::List(l), Self::List(r)) => {
+                for (first, second) in l.iter().zip(r.iter()) {
+                    if let Self::Bool(true) = Self::gt(first.clone(), second.clone()) {
+                        return Self::Bool(true);
+                    }
+                    if let Self::Bool(false) = Self::eq(first.clone(), second.clone()) {
+                        return Self::Bool(false);
+                    }
+                }
+                Self::Bool(l.len() > r.len())
+            }
+            (Self::Obj(l), Self::Obj(r)) => {
+                let mut lkeys: Vec<&String> = l.keys().collect();
+                let mut rkeys: Vec<&String> = r.keys().collect();
+                lkeys.sort();
+                rkeys.sort();
+                if lkeys != rkeys {
+                    return Self::Bool(lkeys > rkeys);
+                }
+                for key in lkeys {
+                    let lv = l.get(key).unwrap().clone();
+                    let rv = r.get(key).unwrap().clone();
+                    if let Self::Bool(true) = Self::gt(lv.clone(), rv.clone()) {
+                        return Self::Bool(true);
+                    }
+                    if let Self::Bool(false) = Self::eq(lv, rv) {
+                        return Self::Bool(false);
+                    }
+                }
+                Self::Bool(false)
+            }
             _ => Self::Bool(false),
         }
     }
 
     pub fn le(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
         match (lhs, rhs) {
             (Self::Int(l), Self::Int(r)) => Self::Bool(l <= r),
             (Self::Float(l), Self::Float(r)) => Self::Bool(l <= r),
@@ -357,11 +889,50 @@ impl QType {
                 Self::Bool(false)
             }
             (Self::Thread(Some(l)), Self::Thread(Some(r))) => Self::Bool(l <= r),
+            // See `lt`'s List/Obj arms for the ordering rules; `le` only
+            // differs in the final length/key-set comparison.
+            (Self::List(l), Self::List(r)) => {
+                for (first, second) in l.iter().zip(r.iter()) {
+                    if let Self::Bool(true) = Self::lt(first.clone(), second.clone()) {
+                        return Self::Bool(true);
+                    }
+                    if let Self::Bool(false) = Self::eq(first.clone(), second.clone()) {
+                        return Self::Bool(false);
+                    }
+                }
+                Self::Bool(l.len() <= r.len())
+            }
+            (Self::Obj(l), Self::Obj(r)) => {
+                let mut lkeys: Vec<&String> = l.keys().collect();
+                let mut rkeys: Vec<&String> = r.keys().collect();
+                lkeys.sort();
+                rkeys.sort();
+                if lkeys != rkeys {
+                    return Self::Bool(lkeys <= rkeys);
+                }
+                for key in lkeys {
+                    let lv = l.get(key).unwrap().clone();
+                    let rv = r.get(key).unwrap().clone();
+                    if let Self::Bool(true) = Self::lt(lv.clone(), rv.clone()) {
+                        return Self::Bool(true);
+                    }
+                    if let Self::Bool(false) = Self::eq(lv, rv) {
+                        return Self::Bool(false);
+                    }
+                }
+                Self::Bool(true)
+            }
             _ => Self::Bool(false),
         }
     }
 
     pub fn ge(lhs: Self, rhs: Self) -> Self {
+        if let e @ Self::Err(_) = lhs {
+            return e;
+        }
+        if let e @ Self::Err(_) = rhs {
+            return e;
+        }
         match (lhs, rhs) {
             (Self::Int(l), Self::Int(r)) => Self::Bool(l >= r),
             (Self::Float(l), Self::Float(r)) => Self::Bool(l >= r),
@@ -370,6 +941,39 @@ impl QType {
                 Self::Bool(false)
             }
             (Self::Thread(Some(l)), Self::Thread(Some(r))) => Self::Bool(l >= r),
+            // See `gt`'s List/Obj arms for the ordering rules; `ge` only
+            // differs in the final length/key-set comparison.
+            (Self::List(l), Self::List(r)) => {
+                for (first, second) in l.iter().zip(r.iter()) {
+                    if let Self::Bool(true) = Self::gt(first.clone(), second.clone()) {
+                        return Self::Bool(true);
+                    }
+                    if let Self::Bool(false) = Self::eq(first.clone(), second.clone()) {
+                        return Self::Bool(false);
+                    }
+                }
+                Self::Bool(l.len() >= r.len())
+            }
+            (Self::Obj(l), Self::Obj(r)) => {
+                let mut lkeys: Vec<&String> = l.keys().collect();
+                let mut rkeys: Vec<&String> = r.keys().collect();
+                lkeys.sort();
+                rkeys.sort();
+                if lkeys != rkeys {
+                    return Self::Bool(lkeys >= rkeys);
+                }
+                for key in lkeys {
+                    let lv = l.get(key).unwrap().clone();
+                    let rv = r.get(key).unwrap().clone();
+                    if let Self::Bool(true) = Self::gt(lv.clone(), rv.clone()) {
+                        return Self::Bool(true);
+                    }
+                    if let Self::Bool(false) = Self::eq(lv, rv) {
+                        return Self::Bool(false);
+                    }
+                }
+                Self::Bool(true)
+            }
             _ => Self::Bool(false),
         }
     }
@@ -390,3 +994,152 @@ fn to_qobj_key(k: QType) -> String {
         _ => panic!("How are you here?"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obj(pairs: impl IntoIterator<Item = (&'static str, QType)>) -> QObject {
+        pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+    }
+
+    fn stream(items: Vec<i64>) -> QType {
+        QType::Stream(Rc::new(RefCell::new(items.into_iter().map(QType::Int))))
+    }
+
+    #[test]
+    fn obj_eq_is_order_independent() {
+        let a = obj([("x", QType::Int(1)), ("y", QType::Int(2))]);
+        let b = obj([("y", QType::Int(2)), ("x", QType::Int(1))]);
+        assert!(matches!(QType::eq(QType::Obj(a), QType::Obj(b)), QType::Bool(true)));
+    }
+
+    #[test]
+    fn obj_eq_rejects_different_values() {
+        let a = obj([("x", QType::Int(1))]);
+        let b = obj([("x", QType::Int(2))]);
+        assert!(matches!(QType::eq(QType::Obj(a), QType::Obj(b)), QType::Bool(false)));
+    }
+
+    #[test]
+    fn obj_eq_rejects_different_key_sets() {
+        let a = obj([("x", QType::Int(1))]);
+        let b = obj([("y", QType::Int(1))]);
+        assert!(matches!(QType::eq(QType::Obj(a), QType::Obj(b)), QType::Bool(false)));
+    }
+
+    #[test]
+    fn list_lt_is_lexicographic() {
+        let a = QType::List(vec![QType::Int(1), QType::Int(2)]);
+        let b = QType::List(vec![QType::Int(1), QType::Int(3)]);
+        assert!(matches!(QType::lt(a.clone(), b.clone()), QType::Bool(true)));
+        assert!(matches!(QType::gt(b, a), QType::Bool(true)));
+    }
+
+    #[test]
+    fn list_lt_treats_shorter_prefix_as_less() {
+        let short = QType::List(vec![QType::Int(1)]);
+        let long = QType::List(vec![QType::Int(1), QType::Int(0)]);
+        assert!(matches!(QType::lt(short.clone(), long.clone()), QType::Bool(true)));
+        assert!(matches!(QType::gt(long, short), QType::Bool(true)));
+    }
+
+    #[test]
+    fn obj_lt_orders_by_sorted_keys_then_values() {
+        let smaller = obj([("a", QType::Int(1)), ("b", QType::Int(1))]);
+        let bigger = obj([("a", QType::Int(1)), ("b", QType::Int(2))]);
+        assert!(matches!(
+            QType::lt(QType::Obj(smaller.clone()), QType::Obj(bigger.clone())),
+            QType::Bool(true)
+        ));
+        assert!(matches!(
+            QType::gt(QType::Obj(bigger), QType::Obj(smaller)),
+            QType::Bool(true)
+        ));
+    }
+
+    #[test]
+    fn eq_propagates_err_operands() {
+        let e = QType::err(QErrorKind::TypeMismatch, "boom");
+        assert!(matches!(QType::eq(e, QType::Int(1)), QType::Err(_)));
+    }
+
+    #[test]
+    fn list_le_ge_are_reflexive() {
+        let l = QType::List(vec![QType::Int(1), QType::Int(2)]);
+        assert!(matches!(QType::le(l.clone(), l.clone()), QType::Bool(true)));
+        assert!(matches!(QType::ge(l.clone(), l), QType::Bool(true)));
+    }
+
+    #[test]
+    fn obj_le_ge_are_reflexive() {
+        let o = obj([("x", QType::Int(1)), ("y", QType::Int(2))]);
+        assert!(matches!(
+            QType::le(QType::Obj(o.clone()), QType::Obj(o.clone())),
+            QType::Bool(true)
+        ));
+        assert!(matches!(QType::ge(QType::Obj(o.clone()), QType::Obj(o)), QType::Bool(true)));
+    }
+
+    #[test]
+    fn list_le_ge_order_like_lt_gt() {
+        let small = QType::List(vec![QType::Int(1)]);
+        let big = QType::List(vec![QType::Int(2)]);
+        assert!(matches!(QType::le(small.clone(), big.clone()), QType::Bool(true)));
+        assert!(matches!(QType::ge(big, small), QType::Bool(true)));
+    }
+
+    #[test]
+    fn stream_index_is_consumed_once() {
+        let s = stream(vec![10, 20, 30]);
+        let QType::Stream(inner) = &s else { unreachable!() };
+        assert!(matches!(QType::index(s.clone(), QType::Int(1)), QType::Int(20)));
+        // Indexing advanced the shared iterator past position 1, so asking
+        // for position 0 now reads what used to be position 2.
+        assert!(matches!(QType::index(s.clone(), QType::Int(0)), QType::Int(30)));
+        assert!(inner.borrow_mut().next().is_none());
+    }
+
+    #[test]
+    fn stream_index_out_of_bounds_is_an_error() {
+        let s = stream(vec![1]);
+        assert!(matches!(
+            QType::index(s, QType::Int(5)),
+            QType::Err(e) if e.kind == QErrorKind::IndexOutOfBounds
+        ));
+    }
+
+    #[test]
+    fn stream_add_chains_left_then_right() {
+        let chained = QType::add(stream(vec![1, 2]), stream(vec![3, 4]));
+        let QType::List(items) = QType::to_list(chained) else {
+            unreachable!()
+        };
+        assert!(matches!(
+            QType::eq(QType::List(items), QType::List(vec![QType::Int(1), QType::Int(2), QType::Int(3), QType::Int(4)])),
+            QType::Bool(true)
+        ));
+    }
+
+    #[test]
+    fn stream_eq_compares_lazily_element_by_element() {
+        assert!(matches!(
+            QType::eq(stream(vec![1, 2, 3]), stream(vec![1, 2, 3])),
+            QType::Bool(true)
+        ));
+        assert!(matches!(
+            QType::eq(stream(vec![1, 2]), stream(vec![1, 3])),
+            QType::Bool(false)
+        ));
+        assert!(matches!(
+            QType::eq(stream(vec![1, 2]), stream(vec![1, 2, 3])),
+            QType::Bool(false)
+        ));
+    }
+
+    #[test]
+    fn stream_lt_treats_shorter_prefix_as_less() {
+        assert!(matches!(QType::lt(stream(vec![1]), stream(vec![1, 0])), QType::Bool(true)));
+        assert!(matches!(QType::lt(stream(vec![1, 2]), stream(vec![1, 3])), QType::Bool(true)));
+    }
+}